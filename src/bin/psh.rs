@@ -1,4 +1,3 @@
-use pshovr;
 use std::io;
 use std::env;
 use std::error::Error;
@@ -11,16 +10,16 @@ use std::error::Error;
 fn get_payload() -> Option<String> {
     let args: Vec<_> = env::args().collect();
     let mut stdin = io::stdin().lock();
-    return _get_payload(&args, &mut stdin);
+    _get_payload(&args, &mut stdin)
 }
-fn _get_payload(args: &Vec<String>, stdin: &mut dyn io::BufRead) -> Option<String> {
+fn _get_payload(args: &[String], stdin: &mut dyn io::BufRead) -> Option<String> {
     // TODO(richo) inject args and stdin so we can test this
     let cli_args = &args[1..];
-    return match cli_args.len() {
+    match cli_args.len() {
         0 => _get_stdin_payload(stdin),
         1 => Some(cli_args[0].clone()),
         _ => Some(cli_args.join(" ")),
-    };
+    }
 }
 
 fn _get_stdin_payload(stdin: &mut dyn io::BufRead) -> Option<String> {
@@ -29,10 +28,10 @@ fn _get_stdin_payload(stdin: &mut dyn io::BufRead) -> Option<String> {
 
     stdin.read_line(&mut buf).ok()?;
 
-    if buf.len() == 0 {
+    if buf.is_empty() {
         return None;
     }
-    return Some(buf);
+    Some(buf)
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
@@ -56,7 +55,7 @@ mod tests {
     #[test]
     fn test_prioritizes_args() {
         let args = vec!("psh".into(), "hello yes".into());
-        let mut stdin = "ignored".to_string();
+        let stdin = "ignored".to_string();
 
         let payload = _get_payload(&args, &mut stdin.as_bytes());
         assert_eq!(payload, Some("hello yes".into()));
@@ -65,7 +64,7 @@ mod tests {
     #[test]
     fn test_uses_stdin() {
         let args = vec!("psh".into());
-        let mut stdin = "this is the input".to_string();
+        let stdin = "this is the input".to_string();
 
         let payload = _get_payload(&args, &mut stdin.as_bytes());
         assert_eq!(payload, Some("this is the input".into()));
@@ -74,7 +73,7 @@ mod tests {
     #[test]
     fn test_flattens_args() {
         let args = vec!("psh".into(), "hello".into(), "yes".into(), "!!!".into());
-        let mut stdin = "ignored".to_string();
+        let stdin = "ignored".to_string();
 
         let payload = _get_payload(&args, &mut stdin.as_bytes());
         assert_eq!(payload, Some("hello yes !!!".into()));
@@ -83,7 +82,7 @@ mod tests {
     #[test]
     fn test_returns_none_with_no_input() {
         let args = vec!("psh".into());
-        let mut stdin = "".to_string();
+        let stdin = String::new();
 
         let payload = _get_payload(&args, &mut stdin.as_bytes());
         assert_eq!(payload, None);
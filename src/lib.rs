@@ -1,10 +1,23 @@
 use redacted_debug::RedactedDebug;
 
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
 use failure::{Error, format_err};
-use reqwest;
-use serde::{Serialize, Serializer};
+use reqwest::multipart;
+use serde::{Deserialize, Serialize, Serializer};
+
+static MESSAGE_API_URL: &str = "https://api.pushover.net/1/messages.json";
+static RECEIPT_API_URL: &str = "https://api.pushover.net/1/receipts";
 
-static MESSAGE_API_URL: &'static str = "https://api.pushover.net/1/messages.json";
+/// Pushover's documented maximum image attachment size, 2.5MB.
+static MAX_ATTACHMENT_SIZE: usize = 2_621_440;
 
 #[derive(Debug)]
 /// The notification with which the notification will be sent.
@@ -34,6 +47,44 @@ impl Serialize for Priority {
     }
 }
 
+#[derive(Debug)]
+/// One of the sounds Pushover's device clients can play for a notification.
+pub enum Sound {
+    Pushover,
+    Bike,
+    Cosmic,
+    Falling,
+    Magic,
+    Mechanical,
+    Siren,
+    SpaceAlarm,
+    /// play no sound at all.
+    None,
+    /// a custom sound uploaded to the user's account, referenced by name.
+    Custom(String),
+}
+
+impl Serialize for Sound {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let name = match self {
+            Sound::Pushover => "pushover",
+            Sound::Bike => "bike",
+            Sound::Cosmic => "cosmic",
+            Sound::Falling => "falling",
+            Sound::Magic => "magic",
+            Sound::Mechanical => "mechanical",
+            Sound::Siren => "siren",
+            Sound::SpaceAlarm => "spacealarm",
+            Sound::None => "none",
+            Sound::Custom(name) => name,
+        };
+        serializer.serialize_str(name)
+    }
+}
+
 #[derive(Serialize, RedactedDebug)]
 pub struct Notification<'a> {
     #[redacted]
@@ -47,8 +98,39 @@ pub struct Notification<'a> {
     url: Option<String>,
     url_title: Option<String>,
     priority: Option<Priority>,
+    // retry/expire are only meaningful for RequireConfirmation (priority 2) messages;
+    // Pushover rejects a priority-2 message that omits them.
+    retry: Option<u32>,
+    expire: Option<u32>,
+    sound: Option<Sound>,
+    html: Option<u8>,
+    monospace: Option<u8>,
+    // An image to upload alongside the message; sent as a multipart file part rather than a form field.
+    #[serde(skip)]
+    attachment: Option<Attachment>,
+    // a Unix timestamp of the message's date and time to display, rather than the time our API received it
+    timestamp: Option<i64>,
+    // seconds after which a delivered (non-emergency) message is auto-deleted from the client
+    ttl: Option<u32>,
     // sound - the name of one of the sounds supported by device clients to override the user's default sound choice
-    // timestamp - a Unix timestamp of your message's date and time to display to the user, rather than the time your message is received by our API
+}
+
+/// An image attachment to upload alongside a notification.
+#[derive(Debug)]
+struct Attachment {
+    data: Vec<u8>,
+    filename: String,
+    mime_type: String,
+}
+
+/// Guess an image MIME type from a filename's extension, falling back to a generic binary type.
+fn guess_mime(filename: &str) -> &'static str {
+    match filename.rsplit('.').next().map(|e| e.to_ascii_lowercase()).as_deref() {
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("png") => "image/png",
+        Some("gif") => "image/gif",
+        _ => "application/octet-stream",
+    }
 }
 
 macro_rules! setter {
@@ -66,6 +148,80 @@ impl<'a> Notification<'a> {
     setter!(url, String, "a supplementary URL to show with your message");
     setter!(url_title, String, "a title for your supplementary URL, otherwise just the URL is shown");
     setter!(priority, Priority, "The notification priority for this message");
+    setter!(retry, u32, "how often (in seconds, minimum 30) to re-alert until a priority-2 message is acknowledged");
+    setter!(expire, u32, "how long (in seconds, maximum 10800) to keep retrying a priority-2 message");
+    setter!(sound, Sound, "the sound to play, overriding the user's default choice");
+    setter!(timestamp, i64, "a Unix timestamp to display for the message, rather than its receipt time");
+    setter!(ttl, u32, "seconds after which a non-emergency message auto-deletes from the client");
+
+    /// render the message body as limited HTML; mutually exclusive with `monospace`
+    pub fn html(mut self, html: bool) -> Notification<'a> {
+        self.html = if html { Some(1) } else { None };
+        self
+    }
+
+    /// render the message body in a monospace font; mutually exclusive with `html`
+    pub fn monospace(mut self, monospace: bool) -> Notification<'a> {
+        self.monospace = if monospace { Some(1) } else { None };
+        self
+    }
+
+    /// Check that the notification's fields are internally consistent before sending.
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.html.is_some() && self.monospace.is_some() {
+            return Err(format_err!("html and monospace formatting are mutually exclusive"));
+        }
+        if let Some(retry) = self.retry {
+            if retry < 30 {
+                return Err(format_err!("retry must be at least 30 seconds, got {}", retry));
+            }
+        }
+        if let Some(expire) = self.expire {
+            if expire > 10800 {
+                return Err(format_err!("expire must be at most 10800 seconds, got {}", expire));
+            }
+        }
+        if let Some(Priority::RequireConfirmation) = self.priority {
+            if self.retry.is_none() || self.expire.is_none() {
+                return Err(format_err!(
+                    "an emergency (priority 2) message requires both retry and expire"
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// attach an image, supplied as raw bytes along with a filename and MIME type
+    pub fn attachment(mut self, data: Vec<u8>, filename: impl Into<String>, mime_type: impl Into<String>) -> Notification<'a> {
+        self.attachment = Some(Attachment {
+            data,
+            filename: filename.into(),
+            mime_type: mime_type.into(),
+        });
+        self
+    }
+
+    /// attach an image read from a path, guessing its MIME type from the file extension
+    pub fn attachment_path(self, path: impl AsRef<Path>) -> Result<Notification<'a>, Error> {
+        let path = path.as_ref();
+        let data = fs::read(path)
+            .map_err(|e| format_err!("Couldn't read attachment {:?}: {:?}", path, e))?;
+        let filename = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| format_err!("Attachment path {:?} has no filename", path))?
+            .to_string();
+        let mime_type = guess_mime(&filename).to_string();
+        Ok(self.attachment(data, filename, mime_type))
+    }
+}
+
+/// A channel a notification can be delivered over.
+///
+/// [`PushoverClient`] is itself the primary transport (the Pushover HTTP API); additional
+/// implementations — such as [`EmailTransport`] — can be registered as fallbacks.
+pub trait Transport {
+    fn deliver(&self, notification: &Notification) -> Result<(), Error>;
 }
 
 #[derive(RedactedDebug)]
@@ -73,6 +229,49 @@ pub struct PushoverClient {
     #[redacted]
     token: String,
     client: reqwest::Client,
+    #[redacted]
+    fallbacks: Vec<Box<dyn Transport>>,
+    dedup: Option<Dedup>,
+}
+
+/// An in-memory record of recently-sent notifications, keyed by a hash of their content, used to
+/// suppress repeated alerts within a TTL window.
+#[derive(Debug)]
+struct Dedup {
+    ttl: Duration,
+    seen: Mutex<HashMap<u64, Instant>>,
+}
+
+impl Dedup {
+    fn key(notification: &Notification) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        notification.user.hash(&mut hasher);
+        notification.title.hash(&mut hasher);
+        notification.message.hash(&mut hasher);
+        notification.url.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Prune expired entries, then report whether this notification was sent within the window.
+    fn is_duplicate(&self, notification: &Notification) -> bool {
+        let key = Dedup::key(notification);
+        let mut seen = self.seen.lock().unwrap();
+        seen.retain(|_, sent| sent.elapsed() < self.ttl);
+        seen.contains_key(&key)
+    }
+
+    fn record(&self, notification: &Notification) {
+        let key = Dedup::key(notification);
+        self.seen.lock().unwrap().insert(key, Instant::now());
+    }
+}
+
+/// The result of a [`PushoverClient::send`] call, distinguishing a delivered message from one
+/// suppressed by the dedup layer.
+#[derive(Debug)]
+pub enum Delivery {
+    Sent(SendResponse),
+    Suppressed,
 }
 
 impl PushoverClient {
@@ -82,27 +281,406 @@ impl PushoverClient {
         PushoverClient {
             token,
             client,
+            fallbacks: Vec::new(),
+            dedup: None,
+        }
+    }
+
+    /// Enable client-side deduplication: a notification whose `(user, title, message, url)` matches
+    /// one sent within `ttl` is suppressed rather than re-sent.
+    pub fn with_dedup(mut self, ttl: Duration) -> PushoverClient {
+        self.dedup = Some(Dedup {
+            ttl,
+            seen: Mutex::new(HashMap::new()),
+        });
+        self
+    }
+
+    /// Register a fallback transport, tried in registration order by [`PushoverClient::notify`]
+    /// when the Pushover POST fails. [`PushoverClient::send`] does not use fallbacks.
+    pub fn with_fallback(mut self, transport: Box<dyn Transport>) -> PushoverClient {
+        self.fallbacks.push(transport);
+        self
+    }
+
+    /// Deliver a notification, falling back to each registered transport in turn if the primary
+    /// Pushover POST fails (network error or a non-success response body).
+    pub fn notify<'a>(&'a self, notification: &'a Notification) -> Result<(), Error> {
+        match self.deliver(notification) {
+            Ok(()) => Ok(()),
+            Err(primary) => {
+                for transport in &self.fallbacks {
+                    if transport.deliver(notification).is_ok() {
+                        return Ok(());
+                    }
+                }
+                Err(primary)
+            }
         }
     }
 
     pub fn build_notification<'a>(&'a self, user: &'a str, message: &'a str) -> Notification<'a> {
         Notification {
             token: &self.token,
-            user: user,
-            message: message,
+            user,
+            message,
             title: None,
             url: None,
             url_title: None,
             priority: None,
+            retry: None,
+            expire: None,
+            sound: None,
+            html: None,
+            monospace: None,
+            attachment: None,
+            timestamp: None,
+            ttl: None,
+        }
+    }
+
+    /// Build the outgoing request for a notification, choosing the multipart path if it carries an
+    /// attachment. Validates the notification and enforces the attachment size limit up front.
+    fn request_for(&self, notification: &Notification) -> Result<reqwest::RequestBuilder, Error> {
+        notification.validate()?;
+        let request = self.client.post(MESSAGE_API_URL);
+        Ok(match &notification.attachment {
+            Some(attachment) => {
+                if attachment.data.len() > MAX_ATTACHMENT_SIZE {
+                    return Err(format_err!(
+                        "Attachment is {} bytes, over Pushover's {} byte limit",
+                        attachment.data.len(),
+                        MAX_ATTACHMENT_SIZE
+                    ));
+                }
+                request.multipart(multipart_form(notification, attachment)?)
+            }
+            None => request.form(&notification),
+        })
+    }
+
+    pub fn send<'a>(&'a self, notification: &'a Notification) -> Result<Delivery, Error> {
+        if let Some(dedup) = &self.dedup {
+            if dedup.is_duplicate(notification) {
+                return Ok(Delivery::Suppressed);
+            }
+        }
+        let mut resp = self
+            .request_for(notification)?
+            .send()
+            .map_err(|e| format_err!("HTTP error: {:?}", e))?;
+        let response: SendResponse = resp
+            .json()
+            .map_err(|e| format_err!("Couldn't parse response: {:?}", e))?;
+        // Only remember messages Pushover actually accepted; recording a rejection (which still
+        // comes back HTTP 200 with status 0) would suppress every identical retry within the TTL.
+        if response.status == 1 {
+            if let Some(dedup) = &self.dedup {
+                dedup.record(notification);
+            }
+        }
+        Ok(Delivery::Sent(response))
+    }
+
+    /// Deliver one notification per recipient, honoring Pushover's per-app quota.
+    ///
+    /// Each message that comes back 429 or 5xx is retried on its own with exponential backoff (up
+    /// to `max_attempts` total tries per recipient). The returned vector holds one entry per user in
+    /// input order, so partial failures stay visible.
+    pub fn send_batch<'a, F>(&self, users: &[&'a str], build: F, max_attempts: u32) -> Vec<BatchResult>
+    where
+        F: Fn(&'a str) -> Notification<'a>,
+    {
+        users
+            .iter()
+            .map(|user| self.send_with_backoff(user, &build, max_attempts))
+            .collect()
+    }
+
+    fn send_with_backoff<'a, F>(&self, user: &'a str, build: &F, max_attempts: u32) -> BatchResult
+    where
+        F: Fn(&'a str) -> Notification<'a>,
+    {
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let notification = build(user);
+            let request = match self.request_for(&notification) {
+                Ok(request) => request,
+                Err(e) => return BatchResult::new(user, None, Err(e)),
+            };
+            match request.send() {
+                Err(e) => {
+                    if attempt < max_attempts {
+                        thread::sleep(backoff_delay(attempt));
+                        continue;
+                    }
+                    return BatchResult::new(user, None, Err(format_err!("HTTP error: {:?}", e)));
+                }
+                Ok(mut resp) => {
+                    let status = resp.status();
+                    let rate_limit = RateLimit::from_headers(resp.headers());
+                    let rate_limited = status == reqwest::StatusCode::TOO_MANY_REQUESTS;
+                    let retryable = rate_limited || status.is_server_error();
+                    if retryable && attempt < max_attempts {
+                        let delay = if rate_limited {
+                            rate_limit_delay(attempt, &rate_limit)
+                        } else {
+                            backoff_delay(attempt)
+                        };
+                        thread::sleep(delay);
+                        continue;
+                    }
+                    let result = resp
+                        .json()
+                        .map_err(|e| format_err!("Couldn't parse response: {:?}", e));
+                    return BatchResult::new(user, Some(rate_limit), result);
+                }
+            }
         }
     }
 
-    pub fn send<'a>(&'a self, notification: &'a Notification) -> Result<reqwest::Response, Error> {
+    /// Poll the status of an emergency (priority 2) notification by its receipt token.
+    ///
+    /// Callers generally loop on this until [`ReceiptStatus::acknowledged`] or
+    /// [`ReceiptStatus::expired`] returns `true`.
+    pub fn poll_receipt(&self, receipt: &str) -> Result<ReceiptStatus, Error> {
+        let url = format!("{}/{}.json?token={}", RECEIPT_API_URL, receipt, self.token);
         self.client
-            .post(MESSAGE_API_URL)
-            .form(&notification)
+            .get(&url)
             .send()
-            .map_err(|e| format_err!("HTTP error: {:?}", e))
+            .map_err(|e| format_err!("HTTP error: {:?}", e))?
+            .json()
+            .map_err(|e| format_err!("Couldn't parse receipt: {:?}", e))
+    }
+}
+
+/// The per-app rate-limit state Pushover reports in its `X-Limit-App-*` response headers.
+#[derive(Debug, Clone)]
+pub struct RateLimit {
+    /// total messages permitted per app per month (`X-Limit-App-Limit`).
+    pub limit: Option<u32>,
+    /// messages remaining in the current window (`X-Limit-App-Remaining`).
+    pub remaining: Option<u32>,
+    /// Unix timestamp at which the quota resets (`X-Limit-App-Reset`).
+    pub reset: Option<i64>,
+}
+
+impl RateLimit {
+    fn from_headers(headers: &reqwest::header::HeaderMap) -> RateLimit {
+        fn header<T: std::str::FromStr>(headers: &reqwest::header::HeaderMap, name: &str) -> Option<T> {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse().ok())
+        }
+        RateLimit {
+            limit: header(headers, "X-Limit-App-Limit"),
+            remaining: header(headers, "X-Limit-App-Remaining"),
+            reset: header(headers, "X-Limit-App-Reset"),
+        }
+    }
+}
+
+/// The outcome of delivering to a single recipient in a [`PushoverClient::send_batch`] call.
+#[derive(Debug)]
+pub struct BatchResult {
+    pub user: String,
+    pub result: Result<SendResponse, Error>,
+    /// the rate-limit headers observed on the final attempt, if a response was received at all.
+    pub rate_limit: Option<RateLimit>,
+}
+
+impl BatchResult {
+    fn new(user: &str, rate_limit: Option<RateLimit>, result: Result<SendResponse, Error>) -> BatchResult {
+        BatchResult {
+            user: user.to_string(),
+            result,
+            rate_limit,
+        }
+    }
+}
+
+/// The longest we'll sit on a single recipient waiting for the app quota to reset, so a reset
+/// timestamp far in the future can't stall the whole batch.
+static RATE_LIMIT_WAIT_CAP: u64 = 300;
+
+/// Choose how long to wait before retrying a message Pushover rejected with HTTP 429.
+///
+/// When the `X-Limit-App-Reset` header tells us when the quota frees up, we wait until then
+/// (bounded by [`RATE_LIMIT_WAIT_CAP`]) rather than retrying on a blind exponential schedule that
+/// might fire while the app is still throttled; otherwise we fall back to plain [`backoff_delay`].
+fn rate_limit_delay(attempt: u32, rate_limit: &RateLimit) -> Duration {
+    let backoff = backoff_delay(attempt);
+    if let Some(reset) = rate_limit.reset {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        let until = reset - now;
+        if until > 0 {
+            let wait = Duration::from_secs((until as u64).min(RATE_LIMIT_WAIT_CAP));
+            return wait.max(backoff);
+        }
+    }
+    backoff
+}
+
+/// Exponential backoff (1s, 2s, 4s, … capped at 60s) plus up-to-one-second of jitter, so a batch
+/// retrying against the same quota doesn't resend in lockstep.
+fn backoff_delay(attempt: u32) -> Duration {
+    let seconds = (1u64 << (attempt - 1).min(6)).min(60);
+    let jitter = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| u64::from(d.subsec_millis()))
+        .unwrap_or(0);
+    Duration::from_secs(seconds) + Duration::from_millis(jitter)
+}
+
+impl Transport for PushoverClient {
+    /// Deliver over the primary Pushover HTTP API, treating a non-success status as a failure.
+    fn deliver(&self, notification: &Notification) -> Result<(), Error> {
+        match self.send(notification)? {
+            Delivery::Suppressed => Ok(()),
+            Delivery::Sent(response) if response.status == 1 => Ok(()),
+            Delivery::Sent(response) => {
+                Err(format_err!("Pushover rejected the message (status {})", response.status))
+            }
+        }
+    }
+}
+
+/// A fallback [`Transport`] that formats the notification into an email sent over an SMTP relay.
+pub struct EmailTransport {
+    relay: String,
+    from: String,
+    to: String,
+    credentials: Option<(String, String)>,
+}
+
+impl EmailTransport {
+    pub fn new(relay: impl Into<String>, from: impl Into<String>, to: impl Into<String>) -> EmailTransport {
+        EmailTransport {
+            relay: relay.into(),
+            from: from.into(),
+            to: to.into(),
+            credentials: None,
+        }
+    }
+
+    /// Authenticate to the SMTP relay with the given username and password.
+    pub fn credentials(mut self, username: impl Into<String>, password: impl Into<String>) -> EmailTransport {
+        self.credentials = Some((username.into(), password.into()));
+        self
+    }
+}
+
+impl Transport for EmailTransport {
+    fn deliver(&self, notification: &Notification) -> Result<(), Error> {
+        use lettre::{SmtpClient, Transport as LettreTransport};
+        use lettre::smtp::authentication::Credentials;
+        use lettre_email::EmailBuilder;
+
+        let subject = notification
+            .title
+            .clone()
+            .unwrap_or_else(|| "Pushover notification".into());
+        let mut body = notification.message.to_string();
+        if let Some(url) = &notification.url {
+            body.push_str("\n\n");
+            body.push_str(url);
+        }
+
+        let email = EmailBuilder::new()
+            .to(self.to.clone())
+            .from(self.from.clone())
+            .subject(subject)
+            .text(body)
+            .build()
+            .map_err(|e| format_err!("Couldn't build email: {:?}", e))?;
+
+        let mut client = SmtpClient::new_simple(&self.relay)
+            .map_err(|e| format_err!("Couldn't reach SMTP relay: {:?}", e))?;
+        if let Some((username, password)) = &self.credentials {
+            client = client.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+
+        client
+            .transport()
+            .send(email.into())
+            .map_err(|e| format_err!("Couldn't send email: {:?}", e))?;
+        Ok(())
+    }
+}
+
+/// Build a multipart form carrying the notification's scalar fields as text parts plus the image as
+/// a file part named `attachment`, mirroring the form the plain `.form()` path would have sent.
+fn multipart_form(notification: &Notification, attachment: &Attachment) -> Result<multipart::Form, Error> {
+    let value = serde_json::to_value(notification)
+        .map_err(|e| format_err!("Couldn't serialize notification: {:?}", e))?;
+    let mut form = multipart::Form::new();
+    if let serde_json::Value::Object(map) = value {
+        for (key, val) in map {
+            let text = match val {
+                serde_json::Value::Null => continue,
+                serde_json::Value::String(s) => s,
+                other => other.to_string(),
+            };
+            form = form.text(key, text);
+        }
+    }
+    let part = multipart::Part::bytes(attachment.data.clone())
+        .file_name(attachment.filename.clone())
+        .mime_str(&attachment.mime_type)
+        .map_err(|e| format_err!("Invalid attachment MIME type: {:?}", e))?;
+    Ok(form.part("attachment", part))
+}
+
+#[derive(Debug, Deserialize)]
+/// The parsed body of a successful `messages.json` call.
+pub struct SendResponse {
+    pub status: u8,
+    pub request: String,
+    /// Only present for emergency (priority 2) messages; pass to [`PushoverClient::poll_receipt`].
+    pub receipt: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+/// The parsed body of a `receipts/{receipt}.json` call.
+pub struct ReceiptStatus {
+    #[serde(default)]
+    acknowledged: u8,
+    #[serde(default)]
+    acknowledged_at: i64,
+    #[serde(default)]
+    expired: u8,
+    #[serde(default)]
+    called_back: u8,
+}
+
+impl ReceiptStatus {
+    /// Whether the user has acknowledged the notification.
+    pub fn acknowledged(&self) -> bool {
+        self.acknowledged == 1
+    }
+
+    /// The Unix timestamp at which the notification was acknowledged, if it has been.
+    pub fn acknowledged_at(&self) -> Option<i64> {
+        if self.acknowledged() {
+            Some(self.acknowledged_at)
+        } else {
+            None
+        }
+    }
+
+    /// Whether the notification stopped retrying without being acknowledged.
+    pub fn expired(&self) -> bool {
+        self.expired == 1
+    }
+
+    /// Whether Pushover has fired the configured acknowledgement callback.
+    pub fn called_back(&self) -> bool {
+        self.called_back == 1
     }
 }
 
@@ -111,7 +689,6 @@ mod tests {
     use super::*;
     use std::env;
 
-    use serde_json;
 
     #[test]
     fn test_serialized_priorities_dtrt() {
@@ -127,11 +704,104 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_serialized_sound_dtrt() {
+        let client = PushoverClient::new("".into());
+        let req = client
+            .build_notification("richo", "test")
+            .sound(Sound::Siren);
+        assert!(
+            serde_json::to_string(&req)
+                .unwrap()
+                .contains("\"sound\":\"siren\""),
+            "Serialization failed"
+        );
+    }
+
+    #[test]
+    fn test_timestamp_omitted_when_unset() {
+        let client = PushoverClient::new("".into());
+        let req = client.build_notification("richo", "test");
+        // The form is what actually hits the API (via reqwest's `.form()`), so assert against the
+        // urlencoded body rather than the JSON rendering, which would spell `None` as `null`.
+        assert!(
+            !serde_urlencoded::to_string(&req).unwrap().contains("timestamp"),
+            "timestamp should be omitted from the form when unset"
+        );
+    }
+
+    #[test]
+    fn test_serialized_timestamp_dtrt() {
+        let client = PushoverClient::new("".into());
+        let req = client
+            .build_notification("richo", "test")
+            .timestamp(1700000000);
+        assert!(
+            serde_urlencoded::to_string(&req)
+                .unwrap()
+                .contains("timestamp=1700000000"),
+            "Serialization failed"
+        );
+    }
+
+    #[test]
+    fn test_serialized_ttl_dtrt() {
+        let client = PushoverClient::new("".into());
+        let req = client.build_notification("richo", "test").ttl(3600);
+        assert!(
+            serde_urlencoded::to_string(&req)
+                .unwrap()
+                .contains("ttl=3600"),
+            "Serialization failed"
+        );
+    }
+
+    #[test]
+    fn test_validate_rejects_html_and_monospace() {
+        let client = PushoverClient::new("".into());
+        let req = client
+            .build_notification("richo", "test")
+            .html(true)
+            .monospace(true);
+        assert!(req.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_requires_retry_and_expire_for_emergency() {
+        let client = PushoverClient::new("".into());
+        let bare = client
+            .build_notification("richo", "test")
+            .priority(Priority::RequireConfirmation);
+        assert!(bare.validate().is_err());
+
+        let full = client
+            .build_notification("richo", "test")
+            .priority(Priority::RequireConfirmation)
+            .retry(60)
+            .expire(3600);
+        assert!(full.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_bounds_retry_and_expire() {
+        let client = PushoverClient::new("".into());
+        assert!(client
+            .build_notification("richo", "test")
+            .retry(10)
+            .validate()
+            .is_err());
+        assert!(client
+            .build_notification("richo", "test")
+            .expire(99999)
+            .validate()
+            .is_err());
+    }
+
     #[test]
     fn test_setters_all_work() -> Result<(), Error> {
         let client = PushoverClient::new("".into());
         let notification = client.build_notification("richo", "this is a test_notification");
-        let out = notification
+        let _out = notification
             .title("test title".into())
             .url("http://butts.lol".into())
             .url_title("loool".into())
@@ -140,6 +810,67 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_dedup_key_depends_on_content() {
+        let client = PushoverClient::new("".into());
+        let a = client.build_notification("richo", "disk full");
+        let same = client.build_notification("richo", "disk full");
+        let other_msg = client.build_notification("richo", "disk ok");
+        let other_user = client.build_notification("someone", "disk full");
+
+        assert_eq!(Dedup::key(&a), Dedup::key(&same));
+        assert_ne!(Dedup::key(&a), Dedup::key(&other_msg));
+        assert_ne!(Dedup::key(&a), Dedup::key(&other_user));
+    }
+
+    #[test]
+    fn test_dedup_is_duplicate_honors_ttl() {
+        let client = PushoverClient::new("".into());
+        let notification = client.build_notification("richo", "disk full");
+
+        let dedup = Dedup {
+            ttl: Duration::from_millis(50),
+            seen: Mutex::new(HashMap::new()),
+        };
+        assert!(!dedup.is_duplicate(&notification));
+        dedup.record(&notification);
+        assert!(dedup.is_duplicate(&notification));
+
+        // once the window lapses the entry is pruned and no longer counts as a duplicate.
+        thread::sleep(Duration::from_millis(60));
+        assert!(!dedup.is_duplicate(&notification));
+    }
+
+    #[test]
+    fn test_backoff_delay_doubles_and_caps() {
+        // 1s, 2s, 4s, 8s … (ignoring the sub-second jitter).
+        assert_eq!(backoff_delay(1).as_secs(), 1);
+        assert_eq!(backoff_delay(2).as_secs(), 2);
+        assert_eq!(backoff_delay(3).as_secs(), 4);
+        assert_eq!(backoff_delay(4).as_secs(), 8);
+        // capped at 60s once the exponential would overshoot.
+        assert_eq!(backoff_delay(20).as_secs(), 60);
+    }
+
+    #[test]
+    fn test_rate_limit_from_headers() {
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert("X-Limit-App-Limit", "10000".parse().unwrap());
+        headers.insert("X-Limit-App-Remaining", "7500".parse().unwrap());
+        headers.insert("X-Limit-App-Reset", "1700000000".parse().unwrap());
+
+        let rate_limit = RateLimit::from_headers(&headers);
+        assert_eq!(rate_limit.limit, Some(10000));
+        assert_eq!(rate_limit.remaining, Some(7500));
+        assert_eq!(rate_limit.reset, Some(1700000000));
+
+        // absent headers parse to None rather than erroring.
+        let empty = RateLimit::from_headers(&reqwest::header::HeaderMap::new());
+        assert_eq!(empty.limit, None);
+        assert_eq!(empty.remaining, None);
+        assert_eq!(empty.reset, None);
+    }
+
     #[test]
     #[ignore]
     fn test_sends_notification() -> Result<(), Error> {